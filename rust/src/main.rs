@@ -1,7 +1,10 @@
-use std::collections::{BTreeMap, HashMap};
-use std::fs::File;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+#[cfg(unix)]
 use std::os::fd::AsRawFd;
-use std::{env, io, ptr, slice};
+use std::path::{Path, PathBuf};
+use std::thread::available_parallelism;
+use std::{env, io, ptr, slice, thread};
 
 #[cfg(test)]
 mod tests;
@@ -10,16 +13,205 @@ mod tests;
 
 const DEFAULT_FILE_PATH: &str = "../measurements.txt";
 
+/// Files smaller than this are processed on the calling thread.
+///
+/// Below this size the cost of spawning worker threads and merging their
+/// partial maps outweighs any gain from parallelism.
+const PARALLEL_THRESHOLD: usize = 4 * 1024 * 1024;
+
+/// Files at or above this size are mapped window-by-window instead of in
+/// one `mmap` call, so resident memory stays bounded and huge inputs don't
+/// fail (or can't even be attempted) on address-space-constrained targets.
+const SEGMENT_THRESHOLD: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Default size of each window mapped by the segmented path, used unless
+/// overridden by the `WINDOW_SIZE_BYTES` environment variable (see
+/// [`window_size`]). Must stay a multiple of the page size, since `mmap`'s
+/// offset argument is page-aligned.
+const WINDOW_SIZE: usize = 256 * 1024 * 1024;
+
+/// Table capacity for [`StationTable`]: a power of two comfortably above
+/// the ~10k distinct stations 1BRC produces, so the table never needs to
+/// resize mid-run. At this capacity, 10k entries sit at a ~61% load
+/// factor, which keeps linear-probe chains short.
+const TABLE_CAPACITY: usize = 16_384;
+const TABLE_MASK: usize = TABLE_CAPACITY - 1;
+
+/// A slot in [`StationTable`]'s open-addressing array: a station name and
+/// its accumulated `(min, sum, count, max)`, or `None` if unoccupied.
+type StationSlot<'a> = Option<(&'a [u8], (i32, i64, usize, i32))>;
+
+/// Open-addressing hash table mapping station name bytes to their
+/// accumulated `(min, sum, count, max)`.
+///
+/// Sized once up front (see [`TABLE_CAPACITY`]) and never resized, unlike a
+/// generic `HashMap`, since 1BRC never has more than ~10k distinct
+/// stations. Keys and values live together in the slot array, so a lookup
+/// touches a single cache line instead of chasing a separate allocation
+/// per bucket. Collisions are resolved with linear probing; stations are
+/// hashed with FNV-1a (see [`fnv1a_hash`]), which is fast and more than
+/// adequate for short, mostly-ASCII station names.
+///
+/// Temperatures are stored in tenths of a degree (`i32`) to keep the hot
+/// path free of floating-point work and rounding drift; see
+/// [`parse_temperature`]. The running sum is widened to `i64` since it can
+/// exceed `i32::MAX` over a billion rows.
+struct StationTable<'a> {
+    slots: Vec<StationSlot<'a>>,
+}
+
+impl<'a> StationTable<'a> {
+    fn new() -> Self {
+        Self {
+            slots: vec![None; TABLE_CAPACITY],
+        }
+    }
+
+    /// Records a single reading for `station`, inserting a fresh entry the
+    /// first time it's seen.
+    fn record(&mut self, station: &'a [u8], temperature: i32) {
+        let mut index = fnv1a_hash(station) as usize & TABLE_MASK;
+
+        for _ in 0..TABLE_CAPACITY {
+            match &mut self.slots[index] {
+                Some((key, stats)) if *key == station => {
+                    stats.0 = stats.0.min(temperature);
+                    stats.1 += temperature as i64;
+                    stats.2 += 1;
+                    stats.3 = stats.3.max(temperature);
+                    return;
+                }
+                Some(_) => index = (index + 1) & TABLE_MASK,
+                None => {
+                    self.slots[index] =
+                        Some((station, (temperature, temperature as i64, 1, temperature)));
+                    return;
+                }
+            }
+        }
+
+        panic!("station table is full (more than {TABLE_CAPACITY} distinct stations)");
+    }
+
+    /// Folds an already-aggregated `(min, sum, count, max)` entry in,
+    /// combining it with any existing entry for `station`. Used to merge
+    /// per-thread/per-file tables together.
+    fn merge_entry(&mut self, station: &'a [u8], (min, sum, count, max): (i32, i64, usize, i32)) {
+        let mut index = fnv1a_hash(station) as usize & TABLE_MASK;
+
+        for _ in 0..TABLE_CAPACITY {
+            match &mut self.slots[index] {
+                Some((key, stats)) if *key == station => {
+                    stats.0 = stats.0.min(min);
+                    stats.1 += sum;
+                    stats.2 += count;
+                    stats.3 = stats.3.max(max);
+                    return;
+                }
+                Some(_) => index = (index + 1) & TABLE_MASK,
+                None => {
+                    self.slots[index] = Some((station, (min, sum, count, max)));
+                    return;
+                }
+            }
+        }
+
+        panic!("station table is full (more than {TABLE_CAPACITY} distinct stations)");
+    }
+
+    /// Returns the accumulated `(min, sum, count, max)` for `station`, or
+    /// `None` if it hasn't been recorded. Only used by tests; production
+    /// code only ever needs to insert/merge and then iterate.
+    #[cfg(test)]
+    fn get(&self, station: &[u8]) -> Option<&(i32, i64, usize, i32)> {
+        let mut index = fnv1a_hash(station) as usize & TABLE_MASK;
+
+        for _ in 0..TABLE_CAPACITY {
+            match &self.slots[index] {
+                Some((key, stats)) if *key == station => return Some(stats),
+                Some(_) => index = (index + 1) & TABLE_MASK,
+                None => return None,
+            }
+        }
+
+        None
+    }
+
+    /// Returns the number of distinct stations recorded so far. Only used
+    /// by tests; production code only ever needs to insert/merge and then
+    /// iterate.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+}
+
+impl StationTable<'static> {
+    /// Like [`StationTable::merge_entry`], but for folding a window's
+    /// partial table into a long-lived accumulator whose keys must
+    /// outlive that particular window's mapping (see
+    /// [`process_file_segmented`]): a station seen for the first time has
+    /// its name copied into its own small leaked buffer instead of
+    /// borrowing `station`, so the caller is free to unmap the window
+    /// right after this call returns.
+    fn merge_owned(&mut self, station: &[u8], (min, sum, count, max): (i32, i64, usize, i32)) {
+        let mut index = fnv1a_hash(station) as usize & TABLE_MASK;
+
+        for _ in 0..TABLE_CAPACITY {
+            match &mut self.slots[index] {
+                Some((key, stats)) if *key == station => {
+                    stats.0 = stats.0.min(min);
+                    stats.1 += sum;
+                    stats.2 += count;
+                    stats.3 = stats.3.max(max);
+                    return;
+                }
+                Some(_) => index = (index + 1) & TABLE_MASK,
+                None => {
+                    let owned: &'static [u8] = Box::leak(station.to_vec().into_boxed_slice());
+                    self.slots[index] = Some((owned, (min, sum, count, max)));
+                    return;
+                }
+            }
+        }
+
+        panic!("station table is full (more than {TABLE_CAPACITY} distinct stations)");
+    }
+}
+
+impl<'a> IntoIterator for StationTable<'a> {
+    type Item = (&'a [u8], (i32, i64, usize, i32));
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<StationSlot<'a>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.slots.into_iter().flatten()
+    }
+}
+
+/// FNV-1a over the raw station name bytes: fast, allocation-free, and more
+/// than adequate for short, mostly-distinct keys like station names.
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
 /// Holds station statistics together with the backing memory map.
 ///
 /// # Why does this exist?
-/// The HashMap keys are `&[u8]` slices that point directly into a
+/// The table's keys are `&[u8]` slices that point directly into a
 /// memory-mapped file. In Rust, references must never outlive the data
 /// they point to.
 ///
 /// This struct ensures:
-/// - The memory map (`_mmap`) stays alive
-/// - All keys in `map` remain valid
+/// - The memory map (`_sources`) stays alive
+/// - All keys in `statistics` remain valid
 ///
 /// # Performance
 /// - Zero allocations
@@ -28,66 +220,250 @@ const DEFAULT_FILE_PATH: &str = "../measurements.txt";
 ///
 /// This is purely a *lifetime anchor* for soundness.
 struct Stats<'a> {
-    /// Memory-mapped file backing all station name slices.
+    /// The backing byte source(s) for all station name slices: normally a
+    /// single memory-mapped view of the file (or, off Unix, an owned
+    /// buffered-read copy), one entry per file after [`merge_file_stats`],
+    /// and no entry when the segmented path in [`process_file`] is used for
+    /// very large files, since its keys are owned copies.
     ///
     /// This field is intentionally unused. Its sole purpose is to
-    /// keep the mmap alive for as long as `map` exists.
-    _mmap: &'a [u8],
+    /// keep the source(s) alive for as long as `statistics` exists.
+    _sources: Vec<&'a [u8]>,
 
     /// Station statistics keyed by station name slices.
-    statistics: HashMap<&'a [u8], (f64, f64, usize, f64)>,
+    statistics: StationTable<'a>,
 }
 
 // -------------------------------------------- Main --------------------------------------------
 
 fn main() {
+    raise_fd_limit();
+
     let args: Vec<String> = env::args().collect();
-    let file_path = if args.len() > 1 {
-        args[1].as_str()
+    let file_paths = if args.len() > 1 {
+        expand_paths(&args[1..])
     } else {
-        DEFAULT_FILE_PATH
+        vec![PathBuf::from(DEFAULT_FILE_PATH)]
     };
-    let file =
-        File::open(file_path).unwrap_or_else(|_| panic!("Could not open {} file", file_path));
-    let stats = process_file(&file);
+
+    if file_paths.is_empty() {
+        panic!("No input files found: glob pattern or directory matched nothing");
+    }
+
+    let files: Vec<File> = file_paths
+        .iter()
+        .map(|path| {
+            File::open(path).unwrap_or_else(|_| panic!("Could not open {} file", path.display()))
+        })
+        .collect();
+
+    let per_file: Vec<Stats<'_>> = files.iter().map(process_file).collect();
+    let stats = merge_file_stats(per_file);
     let output = format_output(stats.statistics);
     println!("{output}");
     println!();
 }
 
+// -------------------------------------------- Input Handling --------------------------------------------
+
+/// Raises the process's soft `RLIMIT_NOFILE` toward the hard limit before
+/// any input files are opened.
+///
+/// Aggregating a billion rows pre-sharded across many files means opening
+/// (and `mmap`-ing) them all at once, which can exhaust the default soft
+/// limit — particularly on macOS, where `setrlimit` additionally refuses a
+/// soft limit above `OPEN_MAX` even when the hard limit reports something
+/// higher (e.g. `RLIM_INFINITY`), so the target is clamped there.
+///
+/// Failure to raise the limit is non-fatal: we just proceed with whatever
+/// the soft limit already allows.
+#[cfg(unix)]
+fn raise_fd_limit() {
+    // SAFETY: libc usage
+    unsafe {
+        let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) != 0 {
+            return;
+        }
+
+        let target = limit.rlim_max;
+        #[cfg(target_os = "macos")]
+        let target = target.min(libc::OPEN_MAX as libc::rlim_t);
+
+        if target <= limit.rlim_cur {
+            return;
+        }
+
+        limit.rlim_cur = target;
+        libc::setrlimit(libc::RLIMIT_NOFILE, &limit);
+    }
+}
+
+#[cfg(not(unix))]
+fn raise_fd_limit() {}
+
+/// Expands each of `args` into a list of concrete file paths: a directory
+/// argument is expanded to the files directly inside it, a pattern
+/// containing `*`/`?` is expanded via [`glob_match`], and anything else is
+/// taken as a literal path. This is what lets a single invocation cover a
+/// billion rows pre-sharded across many files.
+fn expand_paths(args: &[String]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    for arg in args {
+        let path = Path::new(arg);
+        if arg.contains('*') || arg.contains('?') {
+            expand_glob(arg, &mut paths);
+        } else if path.is_dir() {
+            expand_directory(path, &mut paths);
+        } else {
+            paths.push(path.to_path_buf());
+        }
+    }
+
+    paths
+}
+
+/// Appends every regular file directly inside `dir` (non-recursive) to
+/// `paths`, in sorted order so runs are reproducible.
+fn expand_directory(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|_| panic!("Could not read {} directory", dir.display()))
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    entries.sort();
+    paths.extend(entries);
+}
+
+/// Appends every file in `pattern`'s directory whose name matches
+/// `pattern`'s final component to `paths`, in sorted order.
+fn expand_glob(pattern: &str, paths: &mut Vec<PathBuf>) {
+    let pattern_path = Path::new(pattern);
+    let dir = pattern_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let file_pattern = pattern_path
+        .file_name()
+        .expect("glob pattern is missing a file name component")
+        .to_string_lossy();
+
+    let mut matches: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|_| panic!("Could not read {} directory", dir.display()))
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| glob_match(file_pattern.as_bytes(), &name.to_string_lossy().into_owned().into_bytes()))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    matches.sort();
+    paths.extend(matches);
+}
+
+/// Matches `name` against a shell-style glob `pattern` supporting `*` (any
+/// run of characters, including none) and `?` (exactly one character). No
+/// external glob crate is pulled in for this: 1BRC shards only ever need
+/// this much wildcard matching over a single directory.
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some(b'*') => {
+            glob_match(&pattern[1..], name)
+                || (!name.is_empty() && glob_match(pattern, &name[1..]))
+        }
+        Some(b'?') => !name.is_empty() && glob_match(&pattern[1..], &name[1..]),
+        Some(&expected) => name.first() == Some(&expected) && glob_match(&pattern[1..], &name[1..]),
+    }
+}
+
 // -------------------------------------------- Helper Functions --------------------------------------------
 
 /// Processes a file and returns aggregated statistics for all stations.
 ///
 /// # Lifetimes
-/// The returned `Stats<'a>` borrows directly from a memory-mapped view
-/// of `file`. All station name keys inside the returned map are slices
-/// pointing into that mapping.
+/// The returned `Stats<'a>` borrows directly from the byte source chosen
+/// for `file` (mmap on Unix, a buffered read elsewhere). All station name
+/// keys inside the returned map are slices pointing into that source,
+/// except on the segmented path for files at or above
+/// [`SEGMENT_THRESHOLD`], whose keys are owned copies.
 ///
-/// The lifetime `'_` ensures the memory map remains valid for as long
+/// The lifetime `'_` ensures the byte source remains valid for as long
 /// as the statistics are used.
 ///
 /// # Design Notes
 /// - Station names are kept as `&[u8]` to avoid UTF‑8 validation and
 ///   allocation during parsing.
+/// - On Unix, `mmap_file` gives a zero-copy view straight from the page
+///   cache. Everywhere else, `mmap` isn't available, so `buffered_file`
+///   reads the whole file into an owned, leaked buffer instead; see its
+///   docs for why that's sound here.
 ///
 /// # Safety
-/// This function relies on `mmap_file`, whose signature does not encode
-/// the true lifetime of the mapping. Correctness is ensured by storing
-/// the returned slice inside `Stats`, preventing it from escaping.
+/// This function relies on `mmap_file`/`buffered_file`, whose signatures
+/// do not encode the true lifetime of their backing memory. Correctness is
+/// ensured by storing the returned slice(s) inside `Stats`, preventing them
+/// from escaping.
 fn process_file(file: &File) -> Stats<'_> {
+    let file_len = file.metadata().expect("Could not read metadata").len();
+
+    #[cfg(unix)]
+    let (statistics, sources): (StationTable<'_>, Vec<&[u8]>) = if file_len >= SEGMENT_THRESHOLD {
+        // The segmented path unmaps each window as it's folded in, so its
+        // keys are independently owned and don't need a source anchor.
+        (process_file_segmented(file, file_len), Vec::new())
+    } else {
+        //note: We know we're going to read the whole file, so buffered reading isn't optimal.
+        // Memory mapping tells the kernel to make the file accessible as memory.
+        let source = mmap_file(file);
+        (process_bytes(source), vec![source])
+    };
+    #[cfg(not(unix))]
+    let (statistics, sources) = {
+        let source = buffered_file(file);
+        (process_bytes(source), vec![source])
+    };
+
+    // The single-shot mapping (or buffered copy) is never unmapped or freed
+    // here; it stays valid until the process exits.
+    Stats {
+        _sources: sources,
+        statistics,
+    }
+}
+
+/// Aggregates a byte slice, picking the single-threaded path for small
+/// inputs and the multithreaded one above [`PARALLEL_THRESHOLD`].
+fn process_bytes(bytes: &[u8]) -> StationTable<'_> {
+    if bytes.len() < PARALLEL_THRESHOLD {
+        process_chunk_single_threaded(bytes)
+    } else {
+        process_chunks_parallel(bytes)
+    }
+}
+
+/// Processes a byte slice on the calling thread, line by line.
+///
+/// This is the original single-threaded path, kept as the fallback for
+/// small inputs and reused by each worker spawned by
+/// [`process_chunks_parallel`].
+fn process_chunk_single_threaded(chunk: &[u8]) -> StationTable<'_> {
     //note: The key is slice of u8 bytes as we already have the data in mmap,
     // there isn't really needed to parse the keys into strings.
     // ~Jon Gjengset:
     //      because it can be references into the mmap,
     //      there's nothing that needs to be owned about.
-    let mut stats = HashMap::<&[u8], (f64, f64, usize, f64)>::new();
+    let mut stats = StationTable::new();
 
-    //note: We know we're going to read the whole file, so buffered reading isn't optimal.
-    // Memory mapping tells the kernel to make the file accessible as memory.
-    let mmap = mmap_file(file);
-
-    for line in mmap.split(|char| *char == b'\n') {
+    for line in chunk.split(|char| *char == b'\n') {
         if line.is_empty() {
             break;
         }
@@ -102,10 +478,109 @@ fn process_file(file: &File) -> Stats<'_> {
         process_line((station, temperature), &mut stats);
     }
 
-    // mmap is automatically unmapped when it goes out of scope (see mmap_file docs)
+    stats
+}
+
+/// Splits `mmap` into `available_parallelism()` roughly-equal byte ranges,
+/// aggregates each range on its own scoped thread, and folds the resulting
+/// per-thread maps into a single combined map.
+///
+/// # Why scoped threads?
+/// Each worker needs to hand back a `StationTable<'_>` whose keys borrow
+/// from `mmap`. A scoped thread (`std::thread::scope`) lets the borrow
+/// checker see that `mmap` outlives every worker, so no `'static` bound or
+/// `Arc` is required.
+fn process_chunks_parallel(mmap: &[u8]) -> StationTable<'_> {
+    let thread_count = available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let ranges = split_into_line_aligned_ranges(mmap, thread_count);
+
+    let partials: Vec<StationTable<'_>> = thread::scope(|scope| {
+        let handles: Vec<_> = ranges
+            .into_iter()
+            .map(|(start, end)| scope.spawn(move || process_chunk_single_threaded(&mmap[start..end])))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    });
+
+    merge_partials(partials)
+}
+
+/// Computes `chunk_count` contiguous `[start, end)` byte ranges covering
+/// `data`, each adjusted forward so it starts right after the previous
+/// range's terminating newline. This guarantees no line is ever split
+/// across two ranges.
+fn split_into_line_aligned_ranges(data: &[u8], chunk_count: usize) -> Vec<(usize, usize)> {
+    if chunk_count <= 1 || data.is_empty() {
+        return vec![(0, data.len())];
+    }
+
+    let approx_chunk_size = data.len() / chunk_count;
+    let mut ranges = Vec::with_capacity(chunk_count);
+    let mut start = 0;
+
+    while start < data.len() {
+        let tentative_end = (start + approx_chunk_size).min(data.len());
+        let end = match data[tentative_end..].iter().position(|&b| b == b'\n') {
+            Some(offset) => tentative_end + offset + 1,
+            None => data.len(),
+        };
+
+        ranges.push((start, end));
+        start = end;
+    }
+
+    ranges
+}
+
+/// Folds a collection of per-thread station maps into one, combining
+/// matching stations with `min`/`max`/`sum`/`count`.
+fn merge_partials(partials: Vec<StationTable<'_>>) -> StationTable<'_> {
+    let mut merged = StationTable::new();
+
+    for partial in partials {
+        merge_into(&mut merged, partial);
+    }
+
+    merged
+}
+
+/// Folds `partial` into `merged` in place, combining matching stations
+/// with `min`/`max`/`sum`/`count`.
+fn merge_into<'a>(merged: &mut StationTable<'a>, partial: StationTable<'a>) {
+    for (station, stats) in partial {
+        merged.merge_entry(station, stats);
+    }
+}
+
+/// Like [`merge_into`], but folds into a [`StationTable::merge_owned`]
+/// accumulator whose keys must outlive `partial`'s backing window. See
+/// [`process_file_segmented`].
+fn merge_into_owned(merged: &mut StationTable<'static>, partial: StationTable<'_>) {
+    for (station, stats) in partial {
+        merged.merge_owned(station, stats);
+    }
+}
+
+/// Combines the per-file `Stats` produced by [`process_file`] — one per
+/// input path — into a single `Stats`, the same way [`merge_partials`]
+/// folds per-thread tables together. Every file's backing source(s) are
+/// carried along so the combined table's keys stay valid.
+fn merge_file_stats(per_file: Vec<Stats<'_>>) -> Stats<'_> {
+    let mut sources = Vec::new();
+    let mut statistics = StationTable::new();
+
+    for stats in per_file {
+        sources.extend(stats._sources);
+        merge_into(&mut statistics, stats.statistics);
+    }
+
     Stats {
-        _mmap: mmap,
-        statistics: stats,
+        _sources: sources,
+        statistics,
     }
 }
 
@@ -143,19 +618,34 @@ fn process_file(file: &File) -> Stats<'_> {
 ///
 /// # Returns
 /// A byte slice (`&[u8]`) referencing the memory-mapped file contents.
+#[cfg(unix)]
 fn mmap_file(file: &File) -> &[u8] {
     let len = file.metadata().expect("Could not read metadata").len();
+    mmap_file_window(file, 0, len as usize)
+}
 
+/// Memory-maps `len` bytes of `file` starting at the page-aligned `offset`,
+/// via `libc::mmap`. [`mmap_file`] is just this with `offset = 0` and
+/// `len` set to the whole file; [`process_file_segmented`] calls this
+/// directly, advancing `offset` window by window, to keep resident memory
+/// bounded for files too large to map in one call.
+///
+/// See [`mmap_file`] for the full safety and soundness notes; the same
+/// caveats apply here.
+///
+/// # Panics
+/// - If `mmap` system call fails (e.g., insufficient memory, invalid file descriptor)
+#[cfg(unix)]
+fn mmap_file_window(file: &File, offset: libc::off_t, len: usize) -> &[u8] {
     // SAFETY: libc usage
     unsafe {
-        const OFFSET: libc::off_t = 0;
         let ptr = libc::mmap(
             ptr::null_mut(),     // Let OS choose address (you don't care where)
-            len as libc::size_t, // Len of file - How many bytes to map
+            len as libc::size_t, // Len of the window to map
             libc::PROT_READ,     // Memory protection: read-only
             libc::MAP_SHARED,    // Changes visible to other processes & persisted to file
             file.as_raw_fd(),    // File descriptor to map
-            OFFSET, // Offset of where we want to read from - Start mapping from beginning of file
+            offset,              // Page-aligned offset of this window into the file
         );
 
         if ptr == libc::MAP_FAILED {
@@ -169,7 +659,7 @@ fn mmap_file(file: &File) -> &[u8] {
         // We're telling the kernel that when we read from a byte
         // offset, we're going to be reading in a sequential order,
         // so feel free to read ahead more (huge ass more) in advance.
-        if libc::madvise(ptr, len as usize, libc::MADV_SEQUENTIAL) != 0 {
+        if libc::madvise(ptr, len, libc::MADV_SEQUENTIAL) != 0 {
             panic!(
                 "failed to advise os on how this memory map will be accessed: {:?}",
                 io::Error::last_os_error()
@@ -177,38 +667,300 @@ fn mmap_file(file: &File) -> &[u8] {
         }
 
         let data = ptr as *const u8;
-        let number_of_elements = len as usize;
-        slice::from_raw_parts(data, number_of_elements)
+        slice::from_raw_parts(data, len)
     }
 }
 
-/// Processes a single line and updates the stats map.
-/// Lifetime specifiers are required because `HashMap` is **invariant**
-/// over its key type when mutably borrowed.
-fn process_line<'a>(
-    line: (&'a [u8], &'a [u8]),
-    stats: &mut HashMap<&'a [u8], (f64, f64, usize, f64)>,
-) {
+/// Resolves a `WINDOW_SIZE_BYTES` override against `page_size`: `mmap`'s
+/// offset argument must be page-aligned, and every window after the first
+/// is mapped at the running sum of previous window lengths, so a
+/// misaligned override would make a later `mmap_file_window` call fail
+/// with a cryptic `EINVAL` instead of a clear one. Parses `raw` and rounds
+/// it down to the nearest multiple of `page_size`; falls back to
+/// [`WINDOW_SIZE`] when `raw` is absent, doesn't parse as a positive
+/// integer, or rounds down to zero.
+fn resolve_window_size(raw: Option<&str>, page_size: usize) -> usize {
+    raw.and_then(|value| value.parse::<usize>().ok())
+        .map(|size| size / page_size * page_size)
+        .filter(|&size| size > 0)
+        .unwrap_or(WINDOW_SIZE)
+}
+
+/// Returns the window size used by [`process_file_segmented`]: the value
+/// of the `WINDOW_SIZE_BYTES` environment variable, page-aligned by
+/// [`resolve_window_size`], or the [`WINDOW_SIZE`] default.
+#[cfg(unix)]
+fn window_size() -> usize {
+    // SAFETY: sysconf with _SC_PAGESIZE just reads a system constant.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize };
+    resolve_window_size(env::var("WINDOW_SIZE_BYTES").ok().as_deref(), page_size)
+}
+
+/// Processes a file too large to map in a single `mmap` call by mapping it
+/// in fixed windows of [`window_size`] bytes, advancing `offset` one window
+/// at a time, folding each window's partial statistics into one accumulator,
+/// and unmapping the window before moving on to the next — so resident
+/// memory stays bounded by the window size rather than growing with every
+/// window visited, which is the whole point of segmenting in the first
+/// place.
+///
+/// # Straddling lines
+/// A window boundary can land in the middle of a record. Whenever a
+/// window doesn't end exactly on a `b'\n'`, the trailing partial record is
+/// copied into a small owned scratch buffer and prepended to the *next*
+/// window's first line before that line is parsed, so no record is ever
+/// corrupted or dropped at a boundary.
+///
+/// # Why the accumulator can outlive each window
+/// [`StationTable::merge_owned`] copies a station's name into its own
+/// leaked buffer the first time it's seen, instead of borrowing from the
+/// window. That decouples the accumulator's keys from any one window's
+/// mapping, which is what makes it sound to unmap a window as soon as its
+/// records (and any carried-over partial record) have been folded in.
+///
+/// # Returns
+/// The merged statistics. Unlike the single-shot `mmap` path, these keys
+/// don't borrow from any window, so there's no source slice to anchor.
+#[cfg(unix)]
+fn process_file_segmented(file: &File, file_len: u64) -> StationTable<'static> {
+    let mut merged = StationTable::new();
+    let mut carry_over: Vec<u8> = Vec::new();
+    let mut offset: u64 = 0;
+    let window_size = window_size() as u64;
+
+    while offset < file_len {
+        let window_len = (file_len - offset).min(window_size) as usize;
+        let window = mmap_file_window(file, offset as libc::off_t, window_len);
+        let is_last_window = offset + window_len as u64 == file_len;
+
+        let split = split_window(&carry_over, window, is_last_window);
+
+        if let Some(stitched) = &split.stitched {
+            merge_into_owned(&mut merged, process_bytes(stitched));
+        }
+        merge_into_owned(&mut merged, process_bytes(split.rest));
+        carry_over = split.carry_forward;
+
+        // Nothing borrows from `window` anymore: its complete records are
+        // folded into `merged` (whose keys are independently owned) and
+        // its trailing partial record has been copied into `carry_over`.
+        unmap_window(window);
+
+        offset += window_len as u64;
+    }
+
+    merged
+}
+
+/// The result of [`split_window`]: what [`process_file_segmented`] should
+/// fold into its accumulator right away, and what to carry into the next
+/// window.
+struct WindowSplit<'a> {
+    /// The previous carry-over stitched onto this window's first line,
+    /// if there was a carry-over to stitch.
+    stitched: Option<Vec<u8>>,
+    /// This window's remaining complete records (after any stitched
+    /// prefix), borrowed directly from the window.
+    rest: &'a [u8],
+    /// Bytes to carry into the next window.
+    carry_forward: Vec<u8>,
+}
+
+/// Decides how to handle one window given the bytes carried over from the
+/// previous window. Pulled out of [`process_file_segmented`] as a pure
+/// function so the carry-over/stitching logic can be unit-tested without
+/// a real `mmap`.
+///
+/// `window` is split at its last `b'\n'` (or treated as fully complete if
+/// `is_last_window`):
+/// - If the window has no newline at all, nothing can be processed yet:
+///   `carry_over` and the whole window are carried forward together, so a
+///   record split across more than two consecutive windows is still
+///   reassembled correctly instead of being flushed early.
+/// - Otherwise, any non-empty `carry_over` is stitched onto the window's
+///   first line into its own owned buffer, and the window's remaining
+///   complete records are returned as-is (borrowed, zero-copy).
+fn split_window<'a>(carry_over: &[u8], window: &'a [u8], is_last_window: bool) -> WindowSplit<'a> {
+    let split_at = if is_last_window {
+        window.len()
+    } else {
+        match window.iter().rposition(|&byte| byte == b'\n') {
+            Some(index) => index + 1,
+            None => 0,
+        }
+    };
+    let (complete_records, trailing_partial) = window.split_at(split_at);
+
+    if complete_records.is_empty() {
+        // No record boundary anywhere in this window: carry the previous
+        // carry-over and the whole window forward together, untouched.
+        let mut carry_forward = carry_over.to_vec();
+        carry_forward.extend_from_slice(window);
+        return WindowSplit {
+            stitched: None,
+            rest: &[],
+            carry_forward,
+        };
+    }
+
+    let (stitched, rest) = if carry_over.is_empty() {
+        (None, complete_records)
+    } else {
+        // Stitch the carried-over partial record onto this window's first line.
+        let first_line_end = complete_records
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .unwrap_or(complete_records.len());
+        let (first_line, rest) = complete_records.split_at(first_line_end);
+
+        let mut stitched = carry_over.to_vec();
+        stitched.extend_from_slice(first_line);
+        (Some(stitched), rest.strip_prefix(b"\n").unwrap_or(rest))
+    };
+
+    WindowSplit {
+        stitched,
+        rest,
+        carry_forward: trailing_partial.to_vec(),
+    }
+}
+
+/// Unmaps a window previously returned by [`mmap_file_window`]. Used by
+/// [`process_file_segmented`] to release each window as soon as nothing
+/// references it anymore, keeping resident memory bounded across the
+/// whole file.
+///
+/// # Panics
+/// - If `munmap` fails.
+///
+/// # Safety
+/// `window` must be exactly the slice returned by a prior
+/// `mmap_file_window` call, and the caller must not read through (or hold
+/// any other reference into) `window` after this call returns.
+#[cfg(unix)]
+fn unmap_window(window: &[u8]) {
+    // SAFETY: caller guarantees `window` is an untouched `mmap_file_window`
+    // mapping that nothing still borrows from.
+    unsafe {
+        if libc::munmap(window.as_ptr() as *mut libc::c_void, window.len()) != 0 {
+            panic!("failed to unmap window: {:?}", io::Error::last_os_error());
+        }
+    }
+}
+
+/// Reads a file into an owned buffer, one page at a time, for targets
+/// without `libc::mmap` (e.g. Windows).
+///
+/// # Why not just `read_to_end`?
+/// Reads go through one reusable page-sized buffer, matching the "read in
+/// fixed windows" shape the segmented mmap path also uses. The output
+/// `Vec` still grows as each page is appended to it.
+///
+/// # Panics
+/// - If a read fails for a reason other than end-of-input
+///
+/// # Returns
+/// A byte slice (`&[u8]`) referencing the file's full contents.
+///
+/// # Leak
+/// The buffer is intentionally leaked (`Box::leak`) to obtain a `&[u8]`
+/// whose lifetime isn't tied to this function's stack frame, exactly as
+/// `mmap_file`'s mapping outlives it on Unix. The process runs once and
+/// exits, so the leaked buffer is reclaimed by the OS on exit.
+#[cfg(not(unix))]
+fn buffered_file(file: &File) -> &[u8] {
+    const PAGE_SIZE: usize = 4096;
+
+    let mut reader = file;
+    let buffer = read_paged(&mut reader, PAGE_SIZE);
+
+    Box::leak(buffer.into_boxed_slice())
+}
+
+/// Reads `reader` to completion into an owned buffer, one `page_size`-sized
+/// page at a time.
+///
+/// Pulled out of [`buffered_file`] as a small generic-over-[`Read`] function
+/// so the read-loop itself (short reads, `Interrupted` retries, detecting
+/// EOF) can be unit-tested on any target, independent of the
+/// `#[cfg(not(unix))]` gate that makes `buffered_file` itself untestable on
+/// this (Unix) development machine.
+///
+/// # Panics
+/// - If a read fails for a reason other than end-of-input
+#[cfg(any(not(unix), test))]
+fn read_paged<R: io::Read>(reader: &mut R, page_size: usize) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut page = vec![0u8; page_size];
+
+    loop {
+        // `Read::read` is only guaranteed to fill as much of `page` as is
+        // conveniently available on a single call, not the whole buffer,
+        // so a short page doesn't necessarily mean EOF — keep calling
+        // `read` until the page is full or it returns `Ok(0)`. (We
+        // deliberately don't use `read_exact`: the standard library
+        // documents its buffer contents as unspecified after an error, so
+        // relying on it to recover a short final page isn't portable.)
+        let mut filled = 0;
+        while filled < page.len() {
+            match reader.read(&mut page[filled..]) {
+                Ok(0) => break,
+                Ok(bytes_read) => filled += bytes_read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => panic!("failed to read file: {error:?}"),
+            }
+        }
+
+        buffer.extend_from_slice(&page[..filled]);
+
+        if filled < page.len() {
+            break;
+        }
+    }
+
+    buffer
+}
+
+/// Processes a single line and records it into the station table.
+fn process_line<'a>(line: (&'a [u8], &'a [u8]), stats: &mut StationTable<'a>) {
     let (station, temperature) = line; // avoid utf-8 parsing except for temperature
-    // SAFETY: 1BRC README.md promised valid utf-8 string characters
-    let temperature = unsafe { str::from_utf8_unchecked(temperature) }
-        .parse::<f64>()
-        .expect("Could not parse temperature");
+    let temperature = parse_temperature(temperature);
+    stats.record(station, temperature);
+}
 
-    // Get or insert default value for the station
-    let entry = stats
-        .entry(station)
-        .or_insert((f64::MAX, 0_f64, 0usize, f64::MIN));
+/// Parses a 1BRC temperature reading into tenths of a degree, e.g.
+/// `b"12.3"` -> `123` and `b"-4.7"` -> `-47`.
+///
+/// # Format
+/// The 1BRC README guarantees every reading matches `-?\d?\d\.\d`: an
+/// optional leading `-`, one or two integer digits, a single `.`, and
+/// exactly one fractional digit. This lets us scan the bytes once,
+/// accumulating `value = value * 10 + digit` and simply skipping the
+/// `b'.'`, which scales the result to tenths for free and avoids the
+/// `f64` parse (and its rounding drift) entirely.
+fn parse_temperature(bytes: &[u8]) -> i32 {
+    let (negative, digits) = match bytes.first() {
+        Some(b'-') => (true, &bytes[1..]),
+        _ => (false, bytes),
+    };
 
-    // Update the min, sum, count, and max values for the station
-    entry.0 = entry.0.min(temperature); // min
-    entry.1 += temperature; // running sum
-    entry.2 += 1; // count
-    entry.3 = entry.3.max(temperature); // max
+    let mut value: i32 = 0;
+    for &byte in digits {
+        if byte == b'.' {
+            continue;
+        }
+        value = value * 10 + (byte - b'0') as i32;
+    }
+
+    if negative {
+        -value
+    } else {
+        value
+    }
 }
 
 /// Formats the statistics into the required output format.
-fn format_output(stats: HashMap<&[u8], (f64, f64, usize, f64)>) -> String {
+fn format_output(stats: StationTable<'_>) -> String {
     // We can;
     // a) sort all the keys,
     // b) move them into BTreeMap
@@ -223,8 +975,14 @@ fn format_output(stats: HashMap<&[u8], (f64, f64, usize, f64)>) -> String {
     let mut stats = stats.iter().peekable();
 
     while let Some((station, (min, sum, count, max))) = stats.next() {
-        let mean = sum / (*count as f64);
-        output.push_str(&format!("{}={:.1}/{:.1}/{:.1}", station, min, mean, max));
+        let mean_tenths = round_half_away_from_zero(*sum, *count as i64);
+        output.push_str(&format!(
+            "{}={}/{}/{}",
+            station,
+            format_tenths(*min as i64),
+            format_tenths(mean_tenths),
+            format_tenths(*max as i64)
+        ));
 
         // Add comma separator if there are more items to come
         if stats.peek().is_some() {
@@ -235,3 +993,24 @@ fn format_output(stats: HashMap<&[u8], (f64, f64, usize, f64)>) -> String {
     output.push('}');
     output
 }
+
+/// Divides `sum` by `count`, rounding half away from zero instead of
+/// truncating toward zero, so the reported mean matches what the old
+/// `f64` + `format!("{:.1}", …)` path produced. Without this, a mean like
+/// `-170 / 3 = -56.66..` truncates to `-56` (`"-5.6"`) instead of
+/// rounding to the nearest tenth, `-57` (`"-5.7"`).
+fn round_half_away_from_zero(sum: i64, count: i64) -> i64 {
+    if sum >= 0 {
+        (sum + count / 2) / count
+    } else {
+        (sum - count / 2) / count
+    }
+}
+
+/// Renders a tenths-scaled value (e.g. `123` for `12.3`) back into its
+/// one-decimal-place display form.
+fn format_tenths(tenths: i64) -> String {
+    let sign = if tenths < 0 { "-" } else { "" };
+    let magnitude = tenths.abs();
+    format!("{sign}{}.{}", magnitude / 10, magnitude % 10)
+}
@@ -1,5 +1,4 @@
 use super::*;
-use std::collections::HashMap;
 use std::io::Write;
 use tempfile::NamedTempFile;
 
@@ -14,7 +13,7 @@ fn test_mmap_file_small_content() {
         .expect("Failed to write to temp file");
     file.flush().expect("Failed to flush");
 
-    let mmap = mmap_file(&file.as_file());
+    let mmap = mmap_file(file.as_file());
 
     assert_eq!(mmap.len(), content.len());
     assert_eq!(mmap, content);
@@ -29,7 +28,7 @@ fn test_mmap_file_unicode_content() {
         .expect("Failed to write to temp file");
     file.flush().expect("Failed to flush");
 
-    let mmap = mmap_file(&file.as_file());
+    let mmap = mmap_file(file.as_file());
 
     assert_eq!(mmap.len(), content.len());
     assert_eq!(mmap, content);
@@ -44,7 +43,7 @@ fn test_mmap_file_large_content() {
         .expect("Failed to write to temp file");
     file.flush().expect("Failed to flush");
 
-    let mmap = mmap_file(&file.as_file());
+    let mmap = mmap_file(file.as_file());
 
     assert_eq!(mmap.len(), content.len());
     // Check first, middle, and last bytes
@@ -58,7 +57,7 @@ fn test_mmap_file_large_content() {
 fn test_line_parsing_with_mmap_data() {
     let file = create_test_file("Station1;10.5\nStation2;-3.2\n\nStation3;0.0\n");
 
-    let mmap = mmap_file(&file.as_file());
+    let mmap = mmap_file(file.as_file());
     let lines: Vec<&[u8]> = mmap.split(|&byte| byte == b'\n').collect();
 
     // The data "Station1;10.5\nStation2;-3.2\n\nStation3;0.0\n" splits into:
@@ -72,102 +71,73 @@ fn test_line_parsing_with_mmap_data() {
     assert_eq!(lines[1], b"Station2;-3.2");
     assert_eq!(lines[2], b""); // Empty line
     assert_eq!(lines[3], b"Station3;0.0");
-    assert_eq!(lines[3], b"Station3;0.0");
     assert_eq!(lines[4], b""); // Trailing newline creates empty segment
 }
 
 #[test]
 fn test_process_line_single_entry() {
-    let mut stats = HashMap::new();
+    let mut stats = StationTable::new();
     process_line(parse_input_to_tuple("Hamburg;12.0"), &mut stats);
 
     assert_eq!(stats.len(), 1);
-    assert!(stats.contains_key("Hamburg".as_bytes()));
-
-    let (min, sum, count, max) = stats.get("Hamburg".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, 120));
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        120
-    ));
+
+    let (min, sum, count, max) = stats.get(b"Hamburg").unwrap();
+    assert_eq!(*min, 120);
+    assert_eq!(*sum, 120);
     assert_eq!(*count, 1);
-    assert!(approx_eq_i16(*max, 120));
+    assert_eq!(*max, 120);
 }
 
 #[test]
 fn test_process_line_multiple_same_station() {
-    let mut stats = HashMap::new();
+    let mut stats = StationTable::new();
     process_line(parse_input_to_tuple("Hamburg;12.0"), &mut stats);
     process_line(parse_input_to_tuple("Hamburg;15.0"), &mut stats);
     process_line(parse_input_to_tuple("Hamburg;9.0"), &mut stats);
 
     assert_eq!(stats.len(), 1);
 
-    let (min, sum, count, max) = stats.get("Hamburg".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, 90)); // 9.0 * 10
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        360
-    )); // 12 + 15 + 9 = 36, *10 = 360
+    let (min, sum, count, max) = stats.get(b"Hamburg").unwrap();
+    assert_eq!(*min, 90); // 9.0 * 10
+    assert_eq!(*sum, 360); // 12 + 15 + 9 = 36, *10 = 360
     assert_eq!(*count, 3);
-    assert!(approx_eq_i16(*max, 150));
+    assert_eq!(*max, 150);
 }
 
 #[test]
 fn test_process_line_multiple_stations() {
-    let mut stats = HashMap::new();
+    let mut stats = StationTable::new();
     process_line(parse_input_to_tuple("Hamburg;12.0"), &mut stats);
     process_line(parse_input_to_tuple("Berlin;20.0"), &mut stats);
     process_line(parse_input_to_tuple("Hamburg;8.0"), &mut stats);
 
     assert_eq!(stats.len(), 2);
-    assert!(stats.contains_key("Hamburg".as_bytes()));
-    assert!(stats.contains_key("Berlin".as_bytes()));
-
-    let (min, sum, count, max) = stats.get("Hamburg".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, 80)); // 8.0 * 10
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        200
-    )); // 12.0 + 8.0 = 20.0, *10 = 200
+
+    let (min, sum, count, max) = stats.get(b"Hamburg").unwrap();
+    assert_eq!(*min, 80); // 8.0 * 10
+    assert_eq!(*sum, 200); // 12.0 + 8.0 = 20.0, *10 = 200
     assert_eq!(*count, 2);
-    assert!(approx_eq_i16(*max, 120));
-
-    let (min, sum, count, max) = stats.get("Berlin".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, 200));
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        200
-    ));
+    assert_eq!(*max, 120);
+
+    let (min, sum, count, max) = stats.get(b"Berlin").unwrap();
+    assert_eq!(*min, 200);
+    assert_eq!(*sum, 200);
     assert_eq!(*count, 1);
-    assert!(approx_eq_i16(*max, 200));
+    assert_eq!(*max, 200);
 }
 
 #[test]
 fn test_process_line_negative_temperatures() {
-    let mut stats = HashMap::new();
+    let mut stats = StationTable::new();
     process_line(parse_input_to_tuple("Oslo;-5.0"), &mut stats);
     process_line(parse_input_to_tuple("Oslo;-10.0"), &mut stats);
     process_line(parse_input_to_tuple("Oslo;-2.0"), &mut stats);
 
-    let (min, sum, count, max) = stats.get("Oslo".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, -100)); // -10.0 * 10
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        -170
-    )); // -17.0 * 10
+    let (min, sum, count, max) = stats.get(b"Oslo").unwrap();
+    assert_eq!(*min, -100); // -10.0 * 10
+    assert_eq!(*sum, -170); // -17.0 * 10
     assert_eq!(*count, 3);
-    assert!(approx_eq_i16(*max, -20)); // -2.0 * 10
+    assert_eq!(*max, -20); // -2.0 * 10
 }
 
 #[test]
@@ -198,8 +168,8 @@ fn test_parse_temperature_single_digit_before_decimal() {
 
 #[test]
 fn test_format_output_single_station() {
-    let mut stats = HashMap::<Vec<u8>, (i16, i64, usize, i16)>::new();
-    stats.insert("Hamburg".as_bytes().to_vec(), (90, 360, 3, 150)); // 9.0, 36.0, 15.0 in tenths
+    let mut stats = StationTable::new();
+    stats.merge_entry(b"Hamburg", (90, 360, 3, 150)); // 9.0, 36.0, 15.0 in tenths
 
     let output = format_output(stats);
     assert_eq!(output, "{Hamburg=9.0/12.0/15.0}");
@@ -207,10 +177,10 @@ fn test_format_output_single_station() {
 
 #[test]
 fn test_format_output_multiple_stations_alphabetical() {
-    let mut stats = HashMap::<Vec<u8>, (i16, i64, usize, i16)>::new();
-    stats.insert("Hamburg".as_bytes().to_vec(), (50, 300, 3, 150)); // 5.0, 30.0, 15.0 in tenths
-    stats.insert("Berlin".as_bytes().to_vec(), (100, 450, 3, 200)); // 10.0, 45.0, 20.0 in tenths
-    stats.insert("Copenhagen".as_bytes().to_vec(), (0, 150, 3, 100)); // 0.0, 15.0, 10.0 in tenths
+    let mut stats = StationTable::new();
+    stats.merge_entry(b"Hamburg", (50, 300, 3, 150)); // 5.0, 30.0, 15.0 in tenths
+    stats.merge_entry(b"Berlin", (100, 450, 3, 200)); // 10.0, 45.0, 20.0 in tenths
+    stats.merge_entry(b"Copenhagen", (0, 150, 3, 100)); // 0.0, 15.0, 10.0 in tenths
 
     let output = format_output(stats);
     // BTreeMap in format_output automatically sorts keys alphabetically
@@ -222,9 +192,9 @@ fn test_format_output_multiple_stations_alphabetical() {
 
 #[test]
 fn test_format_output_decimal_precision() {
-    let mut stats = HashMap::<Vec<u8>, (i16, i64, usize, i16)>::new();
-    // sum=766, count=3, mean should be 255 (in tenths) = 25.5 (rounded to 1 decimal)
-    stats.insert("Tokyo".as_bytes().to_vec(), (248, 766, 3, 263)); // 24.8, 76.6, 26.3 in tenths
+    let mut stats = StationTable::new();
+    // sum=766, count=3, mean rounds to 255 (in tenths) = 25.5
+    stats.merge_entry(b"Tokyo", (248, 766, 3, 263)); // 24.8, 76.6, 26.3 in tenths
 
     let output = format_output(stats);
     assert_eq!(output, "{Tokyo=24.8/25.5/26.3}");
@@ -232,46 +202,330 @@ fn test_format_output_decimal_precision() {
 
 #[test]
 fn test_format_output_empty() {
-    let stats = HashMap::new();
+    let stats = StationTable::new();
     let output = format_output(stats);
     assert_eq!(output, "{}");
 }
 
+#[test]
+fn test_split_into_line_aligned_ranges_single_chunk() {
+    let data = b"Hamburg;12.0\nBerlin;20.0\n";
+    assert_eq!(split_into_line_aligned_ranges(data, 1), vec![(0, data.len())]);
+    assert_eq!(split_into_line_aligned_ranges(data, 0), vec![(0, data.len())]);
+}
+
+#[test]
+fn test_split_into_line_aligned_ranges_empty_data() {
+    assert_eq!(split_into_line_aligned_ranges(b"", 4), vec![(0, 0)]);
+}
+
+#[test]
+fn test_split_into_line_aligned_ranges_never_splits_a_line() {
+    let mut data = String::new();
+    for i in 0..500 {
+        data.push_str(&format!("Station{i};{}.0\n", i % 100));
+    }
+    let bytes = data.as_bytes();
+
+    let ranges = split_into_line_aligned_ranges(bytes, 7);
+
+    // Ranges are contiguous and reconstruct the input exactly.
+    assert_eq!(ranges[0].0, 0);
+    for window in ranges.windows(2) {
+        assert_eq!(window[0].1, window[1].0);
+    }
+    assert_eq!(ranges.last().unwrap().1, bytes.len());
+
+    // Every range ends right after a `b'\n'` (or at EOF), so no line is
+    // ever split across two ranges.
+    for &(_, end) in &ranges {
+        assert!(end == bytes.len() || bytes[end - 1] == b'\n');
+    }
+}
+
+#[test]
+fn test_process_chunks_parallel_matches_single_threaded() {
+    // Build input deliberately at or above `PARALLEL_THRESHOLD`, re-using
+    // `format_tenths` so every generated temperature is already in the
+    // format `parse_temperature` expects.
+    let stations = ["Hamburg", "Berlin", "Oslo", "Tokyo", "Cairo", "Lima"];
+    let mut data = String::new();
+    let mut i: usize = 0;
+    while data.len() < PARALLEL_THRESHOLD {
+        let station = stations[i % stations.len()];
+        let tenths = (i % 1999) as i64 - 999; // covers -99.9..=99.9
+        data.push_str(&format!("{station};{}\n", format_tenths(tenths)));
+        i += 1;
+    }
+    let bytes = data.as_bytes();
+    assert!(
+        bytes.len() >= PARALLEL_THRESHOLD,
+        "test input must actually trigger the parallel path"
+    );
+
+    let parallel = process_chunks_parallel(bytes);
+    let single_threaded = process_chunk_single_threaded(bytes);
+
+    // `format_output` renders a table's contents as a sorted string, so
+    // comparing through it is a convenient way to assert the parallel and
+    // single-threaded paths produce identical aggregates.
+    assert_eq!(format_output(parallel), format_output(single_threaded));
+}
+
+#[test]
+fn test_split_window_no_carry_over() {
+    let split = split_window(b"", b"Hamburg;12.0\nBerlin;20.0\n", false);
+
+    assert!(split.stitched.is_none());
+    assert_eq!(split.rest, b"Hamburg;12.0\nBerlin;20.0\n");
+    assert_eq!(split.carry_forward, b"");
+}
+
+#[test]
+fn test_split_window_stitches_carry_over_onto_first_line() {
+    // "Hamburg;12.0\n" arrives as carry-over "Hamburg;1" plus this
+    // window's "2.0\nBerlin;20.0\n".
+    let split = split_window(b"Hamburg;1", b"2.0\nBerlin;20.0\n", false);
+
+    assert_eq!(split.stitched, Some(b"Hamburg;12.0".to_vec()));
+    assert_eq!(split.rest, b"Berlin;20.0\n");
+    assert_eq!(split.carry_forward, b"");
+}
+
+#[test]
+fn test_split_window_keeps_trailing_partial_record_for_next_window() {
+    let split = split_window(b"", b"Hamburg;12.0\nBerlin;2", false);
+
+    assert!(split.stitched.is_none());
+    assert_eq!(split.rest, b"Hamburg;12.0\n");
+    assert_eq!(split.carry_forward, b"Berlin;2");
+}
+
+#[test]
+fn test_split_window_without_any_newline_carries_everything_forward() {
+    // A window with no newline at all can't yield any complete record,
+    // regardless of whether there was already a carry-over: both must be
+    // carried forward together, untouched, rather than flushed early.
+    let split = split_window(b"Hamburg;1", b"2.0Berlin", false);
+
+    assert!(split.stitched.is_none());
+    assert_eq!(split.rest, b"");
+    assert_eq!(split.carry_forward, b"Hamburg;12.0Berlin");
+}
+
+#[test]
+fn test_split_window_reassembles_record_split_across_three_windows() {
+    // Regression test: a record split across more than two consecutive
+    // windows (none of the middle windows contain a newline) must still
+    // be reassembled correctly, not flushed as an incomplete fragment.
+    let first = split_window(b"", b"Ham", false);
+    assert!(first.stitched.is_none());
+    assert_eq!(first.rest, b"");
+    assert_eq!(first.carry_forward, b"Ham");
+
+    let second = split_window(&first.carry_forward, b"burg;1", false);
+    assert!(second.stitched.is_none());
+    assert_eq!(second.rest, b"");
+    assert_eq!(second.carry_forward, b"Hamburg;1");
+
+    let third = split_window(&second.carry_forward, b"2.0\n", true);
+    assert_eq!(third.stitched, Some(b"Hamburg;12.0".to_vec()));
+    assert_eq!(third.rest, b"");
+    assert_eq!(third.carry_forward, b"");
+}
+
+#[test]
+fn test_split_window_last_window_has_no_trailing_newline() {
+    // The last window doesn't need a trailing `\n` to be treated as complete.
+    let split = split_window(b"", b"Hamburg;12.0", true);
+
+    assert!(split.stitched.is_none());
+    assert_eq!(split.rest, b"Hamburg;12.0");
+    assert_eq!(split.carry_forward, b"");
+}
+
+/// A [`std::io::Read`] mock that serves `chunks` one at a time (each `read`
+/// call returns at most one chunk's worth of bytes, modelling a short read),
+/// optionally interleaving `Err(Interrupted)` at given chunk indices so the
+/// retry path in [`read_paged`] gets exercised too.
+struct ScriptedReader {
+    chunks: std::collections::VecDeque<Vec<u8>>,
+    interrupt_before: std::collections::HashSet<usize>,
+    next_chunk_index: usize,
+}
+
+impl ScriptedReader {
+    fn new(chunks: Vec<Vec<u8>>) -> Self {
+        ScriptedReader {
+            chunks: chunks.into(),
+            interrupt_before: std::collections::HashSet::new(),
+            next_chunk_index: 0,
+        }
+    }
+
+    fn with_interrupt_before(mut self, chunk_index: usize) -> Self {
+        self.interrupt_before.insert(chunk_index);
+        self
+    }
+}
+
+impl std::io::Read for ScriptedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.interrupt_before.remove(&self.next_chunk_index) {
+            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        }
+
+        match self.chunks.pop_front() {
+            Some(chunk) => {
+                self.next_chunk_index += 1;
+                buf[..chunk.len()].copy_from_slice(&chunk);
+                Ok(chunk.len())
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_read_paged_assembles_buffer_from_short_reads() {
+    let reader = ScriptedReader::new(vec![b"Ham".to_vec(), b"burg;".to_vec(), b"12.0\n".to_vec()]);
+
+    let buffer = read_paged(&mut { reader }, 4096);
+
+    assert_eq!(buffer, b"Hamburg;12.0\n");
+}
+
+#[test]
+fn test_read_paged_retries_on_interrupted() {
+    let reader = ScriptedReader::new(vec![b"Hamburg;12.0\n".to_vec()]).with_interrupt_before(0);
+
+    let buffer = read_paged(&mut { reader }, 4096);
+
+    assert_eq!(buffer, b"Hamburg;12.0\n");
+}
+
+#[test]
+fn test_read_paged_stops_at_eof() {
+    let reader = ScriptedReader::new(vec![b"Hamburg;12.0\n".to_vec()]);
+
+    let buffer = read_paged(&mut { reader }, 4096);
+
+    assert_eq!(buffer, b"Hamburg;12.0\n");
+    // A further call against the same (now-exhausted) reader state would
+    // just keep returning `Ok(0)`; nothing left to assemble.
+}
+
+#[test]
+fn test_read_paged_continues_after_full_page() {
+    // A chunk that exactly fills one page, followed by a second page's
+    // worth of data, confirms a full page isn't mistaken for EOF.
+    let reader = ScriptedReader::new(vec![vec![b'A'; 4], vec![b'B'; 2]]);
+
+    let buffer = read_paged(&mut { reader }, 4);
+
+    assert_eq!(buffer, [vec![b'A'; 4], vec![b'B'; 2]].concat());
+}
+
+#[test]
+fn test_resolve_window_size_rounds_down_misaligned_override() {
+    // 100 isn't a multiple of the (simulated) 4096-byte page size; it
+    // should round down to 0 and fall back to WINDOW_SIZE rather than
+    // being passed straight through to mmap, which would reject it.
+    assert_eq!(resolve_window_size(Some("100"), 4096), WINDOW_SIZE);
+
+    // 10000 rounds down to the nearest page boundary instead of falling
+    // back, since it's a valid (if unaligned) request.
+    assert_eq!(resolve_window_size(Some("10000"), 4096), 8192);
+}
+
+#[test]
+fn test_resolve_window_size_accepts_aligned_override() {
+    assert_eq!(resolve_window_size(Some("8192"), 4096), 8192);
+}
+
+#[test]
+fn test_resolve_window_size_falls_back_on_missing_or_invalid_override() {
+    assert_eq!(resolve_window_size(None, 4096), WINDOW_SIZE);
+    assert_eq!(resolve_window_size(Some("not-a-number"), 4096), WINDOW_SIZE);
+    assert_eq!(resolve_window_size(Some("0"), 4096), WINDOW_SIZE);
+    assert_eq!(resolve_window_size(Some("-5"), 4096), WINDOW_SIZE);
+}
+
+#[test]
+fn test_glob_match_literal_pattern() {
+    assert!(glob_match(b"measurements.txt", b"measurements.txt"));
+    assert!(!glob_match(b"measurements.txt", b"measurements.csv"));
+}
+
+#[test]
+fn test_glob_match_star_matches_any_run() {
+    assert!(glob_match(b"measurements-*.txt", b"measurements-1.txt"));
+    assert!(glob_match(b"measurements-*.txt", b"measurements-123.txt"));
+    // `*` also matches zero characters.
+    assert!(glob_match(b"measurements-*.txt", b"measurements-.txt"));
+    assert!(!glob_match(b"measurements-*.txt", b"measurements-1.csv"));
+}
+
+#[test]
+fn test_glob_match_question_mark_matches_exactly_one_character() {
+    assert!(glob_match(b"part-?.txt", b"part-1.txt"));
+    assert!(!glob_match(b"part-?.txt", b"part-12.txt"));
+    assert!(!glob_match(b"part-?.txt", b"part-.txt"));
+}
+
+#[test]
+fn test_glob_match_empty_pattern_only_matches_empty_name() {
+    assert!(glob_match(b"", b""));
+    assert!(!glob_match(b"", b"a"));
+}
+
+#[test]
+fn test_expand_glob_no_match_yields_empty_paths() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(dir.path().join("measurements-1.txt"), b"Hamburg;1.0\n")
+        .expect("Failed to write temp file");
+
+    let pattern = dir.path().join("measurments-*.txt");
+    let mut paths = Vec::new();
+    expand_glob(&pattern.to_string_lossy(), &mut paths);
+
+    assert!(paths.is_empty());
+}
+
+#[test]
+fn test_expand_directory_empty_dir_yields_empty_paths() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+    let mut paths = Vec::new();
+    expand_directory(dir.path(), &mut paths);
+
+    assert!(paths.is_empty());
+}
+
 // -------------------------------------------- Integration Tests --------------------------------------------
 
 #[test]
 fn test_process_file_integration() {
     let data = "Hamburg;12.0\nBerlin;20.0\nHamburg;8.0\nBerlin;25.0\n";
     let file = create_test_file(data);
-    let file_path = file.path().to_str().unwrap();
 
-    let stats = process_file(file_path);
+    let stats = process_file(file.as_file());
 
-    assert_eq!(stats.len(), 2);
+    assert_eq!(stats.statistics.len(), 2);
 
-    // Hamburg: min=8.0*10=80, sum=(12.0+8.0)*10=200, count=2, max=12.0*10=120, mean=200/2/10=10.0
-    let (min, sum, count, max) = stats.get("Hamburg".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, 80));
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        200
-    ));
+    // Hamburg: min=8.0*10=80, sum=(12.0+8.0)*10=200, count=2, max=12.0*10=120
+    let (min, sum, count, max) = stats.statistics.get(b"Hamburg").unwrap();
+    assert_eq!(*min, 80);
+    assert_eq!(*sum, 200);
     assert_eq!(*count, 2);
-    assert!(approx_eq_i16(*max, 120));
-
-    // Berlin: min=20.0*10=200, sum=(20.0+25.0)*10=450, count=2, max=25.0*10=250, mean=450/2/10=22.5
-    let (min, sum, count, max) = stats.get("Berlin".as_bytes()).unwrap();
-    assert!(approx_eq_i16(*min, 200));
-    assert!(approx_eq_i16(
-        (*sum)
-            .try_into()
-            .expect("should be able to convert sum to i64"),
-        450
-    ));
+    assert_eq!(*max, 120);
+
+    // Berlin: min=20.0*10=200, sum=(20.0+25.0)*10=450, count=2, max=25.0*10=250
+    let (min, sum, count, max) = stats.statistics.get(b"Berlin").unwrap();
+    assert_eq!(*min, 200);
+    assert_eq!(*sum, 450);
     assert_eq!(*count, 2);
-    assert!(approx_eq_i16(*max, 250));
+    assert_eq!(*max, 250);
 }
 
 #[test]
@@ -281,24 +535,23 @@ fn test_process_file_with_mmap_integration() {
     let mut file = NamedTempFile::new().expect("Failed to create temp file");
     file.write_all(data.as_bytes())
         .expect("Failed to write to temp file");
-    let file_path = file.path().to_str().unwrap();
+    file.flush().expect("Failed to flush");
 
-    let stats = process_file(file_path);
+    let stats = process_file(file.as_file());
 
-    assert_eq!(stats.len(), 3);
-    assert!(stats.contains_key("A".as_bytes()));
-    assert!(stats.contains_key("B".as_bytes()));
-    assert!(stats.contains_key("C".as_bytes()));
+    assert_eq!(stats.statistics.len(), 3);
+    assert!(stats.statistics.get(b"A").is_some());
+    assert!(stats.statistics.get(b"B").is_some());
+    assert!(stats.statistics.get(b"C").is_some());
 }
 
 #[test]
 fn test_full_pipeline() {
     let data = "Hamburg;12.0\nBerlin;20.0\nHamburg;8.0\nBerlin;25.0\n";
     let file = create_test_file(data);
-    let file_path = file.path().to_str().unwrap();
 
-    let stats = process_file(file_path);
-    let output = format_output(stats);
+    let stats = process_file(file.as_file());
+    let output = format_output(stats.statistics);
 
     assert_eq!(output, "{Berlin=20.0/22.5/25.0, Hamburg=8.0/10.0/12.0}");
 }
@@ -307,15 +560,28 @@ fn test_full_pipeline() {
 fn test_full_pipeline_with_negatives() {
     let data = "Oslo;-5.0\nOslo;-10.0\nOslo;-2.0\n";
     let file = create_test_file(data);
-    let file_path = file.path().to_str().unwrap();
 
-    let stats = process_file(file_path);
-    let output = format_output(stats);
+    let stats = process_file(file.as_file());
+    let output = format_output(stats.statistics);
 
     // mean = -17.0 / 3 = -5.666... rounds to -5.7
     assert_eq!(output, "{Oslo=-10.0/-5.7/-2.0}");
 }
 
+#[test]
+fn test_merge_file_stats_sums_stations_occurring_in_multiple_files() {
+    let file_a = create_test_file("Hamburg;12.0\nBerlin;20.0\n");
+    let file_b = create_test_file("Hamburg;8.0\nOslo;-5.0\n");
+
+    let stats_a = process_file(file_a.as_file());
+    let stats_b = process_file(file_b.as_file());
+
+    let merged = merge_file_stats(vec![stats_a, stats_b]);
+    let output = format_output(merged.statistics);
+
+    assert_eq!(output, "{Berlin=20.0/20.0/20.0, Hamburg=8.0/10.0/12.0, Oslo=-5.0/-5.0/-5.0}");
+}
+
 // -------------------------------------------- Test Helper Functions --------------------------------------------
 
 /// Creates a temporary file with test data for measurements.
@@ -323,15 +589,11 @@ fn create_test_file(data: &str) -> NamedTempFile {
     let mut file = NamedTempFile::new().expect("Failed to create temp file");
     file.write_all(data.as_bytes())
         .expect("Failed to write to temp file");
+    file.flush().expect("Failed to flush");
     file
 }
 
-/// Checks if two i16 values are approximately equal (within 1 unit).
-fn approx_eq_i16(a: i16, b: i16) -> bool {
-    (a - b).abs() <= 1 // Allow tolerance of 1 for rounding differences
-}
-
-/// Parses an input string into a tuple of u8.
+/// Parses an input string into a tuple of byte slices.
 fn parse_input_to_tuple(input: &str) -> (&[u8], &[u8]) {
     let (city, temp) = input.split_once(';').expect("Invalid input format");
     (city.as_bytes(), temp.as_bytes())